@@ -0,0 +1,145 @@
+#![allow(non_upper_case_globals)]
+
+use std::os::raw::c_void;
+use std::ptr;
+
+use core_foundation::base::{
+    OSStatus,
+    TCFType,
+};
+use core_foundation::string::CFString;
+
+use coremidi_sys::{
+    MIDIClientCreate,
+    MIDIClientDispose,
+    MIDIClientRef,
+    MIDINotification,
+};
+
+use notifications::{
+    Notification,
+    NotificationFilter,
+};
+
+/// A callback invoked with each parsed `Notification` delivered by CoreMIDI.
+///
+pub type NotifyCallback = dyn FnMut(&Notification) + Send;
+
+struct NotifyContext {
+    filter: NotificationFilter,
+    callback: Box<NotifyCallback>,
+}
+
+/// A [MIDI client](https://developer.apple.com/reference/coremidi/midiclientref).
+///
+/// A simple example to create a client without notifications:
+///
+/// ```no_run
+/// let client = coremidi::Client::new("example-client").unwrap();
+/// ```
+///
+/// A client that reacts to system notifications:
+///
+/// ```no_run
+/// let client = coremidi::Client::new_with_notifications("example-client", |notification| {
+///     println!("Got a notification: {:?}", notification);
+/// }).unwrap();
+/// ```
+///
+pub struct Client {
+    client_ref: MIDIClientRef,
+    notify_context: Option<Box<NotifyContext>>,
+}
+
+unsafe impl Send for Client {}
+
+impl Client {
+    /// Creates a new CoreMIDI client with no notification handler.
+    /// See [MIDIClientCreate](https://developer.apple.com/reference/coremidi/1495343-midiclientcreate).
+    ///
+    pub fn new(name: &str) -> Result<Self, OSStatus> {
+        let client_name = CFString::new(name);
+        let mut client_ref: MIDIClientRef = 0;
+        let os_status = unsafe {
+            MIDIClientCreate(
+                client_name.as_concrete_TypeRef(),
+                None,
+                ptr::null_mut(),
+                &mut client_ref,
+            )
+        };
+        if os_status == 0 {
+            Ok(Self {
+                client_ref,
+                notify_context: None,
+            })
+        } else {
+            Err(os_status)
+        }
+    }
+
+    /// Creates a new CoreMIDI client that delivers every parsed
+    /// `Notification` to `callback` as it arrives from the system.
+    /// See [MIDIClientCreate](https://developer.apple.com/reference/coremidi/1495343-midiclientcreate).
+    ///
+    /// CoreMIDI invokes the underlying `notifyProc` on the run loop the
+    /// client was created on, so `callback` should not block.
+    ///
+    pub fn new_with_notifications<F>(name: &str, callback: F) -> Result<Self, OSStatus>
+    where
+        F: FnMut(&Notification) + Send + 'static,
+    {
+        Self::new_with_notifications_filtered(name, NotificationFilter::all(), callback)
+    }
+
+    /// Like [`new_with_notifications`](Self::new_with_notifications), but
+    /// only categories included in `filter` are parsed and delivered to
+    /// `callback`; the rest are skipped before parsing.
+    ///
+    pub fn new_with_notifications_filtered<F>(
+        name: &str,
+        filter: NotificationFilter,
+        callback: F,
+    ) -> Result<Self, OSStatus>
+    where
+        F: FnMut(&Notification) + Send + 'static,
+    {
+        let client_name = CFString::new(name);
+        let mut client_ref: MIDIClientRef = 0;
+        let mut notify_context = Box::new(NotifyContext {
+            filter,
+            callback: Box::new(callback),
+        });
+        let ref_con = &mut *notify_context as *mut NotifyContext as *mut c_void;
+        let os_status = unsafe {
+            MIDIClientCreate(
+                client_name.as_concrete_TypeRef(),
+                Some(notify_proc),
+                ref_con,
+                &mut client_ref,
+            )
+        };
+        if os_status == 0 {
+            Ok(Self {
+                client_ref,
+                notify_context: Some(notify_context),
+            })
+        } else {
+            Err(os_status)
+        }
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        unsafe { MIDIClientDispose(self.client_ref) };
+    }
+}
+
+extern "C" fn notify_proc(notification: *const MIDINotification, ref_con: *mut c_void) {
+    let context = unsafe { &mut *(ref_con as *mut NotifyContext) };
+    let notification = unsafe { &*notification };
+    if let Some(Ok(notification)) = Notification::from_filtered(notification, context.filter) {
+        (context.callback)(&notification);
+    }
+}
@@ -23,7 +23,11 @@ use coremidi_sys::{
     MIDIObjectPropertyChangeNotification,
 };
 
-use object::ObjectType;
+use object::{
+    ObjectType,
+    PropertyName,
+    PropertyValue,
+};
 use Device;
 use Object;
 
@@ -40,6 +44,36 @@ pub struct PropertyChangedInfo {
     pub object: Object,
     pub object_type: ObjectType,
     pub property_name: String,
+    pub property: PropertyName,
+}
+
+impl PropertyChangedInfo {
+    /// Re-reads the changed property's current value from `self.object`,
+    /// using the CoreMIDI type that corresponds to `self.property`.
+    /// Returns `None` if the property is not one of the well-known
+    /// properties this crate has a typed accessor for, or if the read
+    /// fails.
+    ///
+    pub fn value(&self) -> Option<PropertyValue> {
+        match self.property {
+            PropertyName::Name | PropertyName::Manufacturer | PropertyName::DisplayName => self
+                .object
+                .get_property_string(self.property.as_str())
+                .ok()
+                .map(PropertyValue::String),
+            PropertyName::UniqueId => self
+                .object
+                .get_property_integer(self.property.as_str())
+                .ok()
+                .map(PropertyValue::Integer),
+            PropertyName::Offline => self
+                .object
+                .get_property_boolean(self.property.as_str())
+                .ok()
+                .map(PropertyValue::Boolean),
+            PropertyName::Other(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -48,6 +82,70 @@ pub struct IOErrorInfo {
     pub error_code: OSStatus,
 }
 
+/// A bitmask selecting which `Notification` categories a subscriber wants
+/// delivered. Passed to `Notification::from_filtered` so that the dispatch
+/// trampoline can skip the expensive parsing (and allocation, e.g. the
+/// `CFString` clone in `from_property_changed`) of categories the caller
+/// didn't ask for.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NotificationFilter(u32);
+
+impl NotificationFilter {
+    pub const SETUP_CHANGED: Self = Self(1 << 0);
+    pub const OBJECT_ADDED: Self = Self(1 << 1);
+    pub const OBJECT_REMOVED: Self = Self(1 << 2);
+    pub const PROPERTY_CHANGED: Self = Self(1 << 3);
+    pub const THRU_CONNECTIONS_CHANGED: Self = Self(1 << 4);
+    pub const SERIAL_PORT_OWNER_CHANGED: Self = Self(1 << 5);
+    pub const IO_ERROR: Self = Self(1 << 6);
+
+    /// A filter matching every notification category.
+    ///
+    pub fn all() -> Self {
+        Self::SETUP_CHANGED
+            | Self::OBJECT_ADDED
+            | Self::OBJECT_REMOVED
+            | Self::PROPERTY_CHANGED
+            | Self::THRU_CONNECTIONS_CHANGED
+            | Self::SERIAL_PORT_OWNER_CHANGED
+            | Self::IO_ERROR
+    }
+
+    /// Whether this filter includes every category in `other`.
+    ///
+    pub fn contains(self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    fn for_message_id(message_id: ::std::os::raw::c_uint) -> Option<Self> {
+        match message_id {
+            kMIDIMsgSetupChanged => Some(Self::SETUP_CHANGED),
+            kMIDIMsgObjectAdded => Some(Self::OBJECT_ADDED),
+            kMIDIMsgObjectRemoved => Some(Self::OBJECT_REMOVED),
+            kMIDIMsgPropertyChanged => Some(Self::PROPERTY_CHANGED),
+            kMIDIMsgThruConnectionsChanged => Some(Self::THRU_CONNECTIONS_CHANGED),
+            kMIDIMsgSerialPortOwnerChanged => Some(Self::SERIAL_PORT_OWNER_CHANGED),
+            kMIDIMsgIOError => Some(Self::IO_ERROR),
+            _ => None,
+        }
+    }
+}
+
+impl Default for NotificationFilter {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl ::std::ops::BitOr for NotificationFilter {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
 /// A message describing a system state change.
 /// See [MIDINotification](https://developer.apple.com/reference/coremidi/midinotification).
 ///
@@ -63,6 +161,20 @@ pub enum Notification {
 }
 
 impl Notification {
+    /// Parses `notification`, unless its category is excluded by `filter`,
+    /// in which case parsing is skipped entirely and `None` is returned.
+    ///
+    pub fn from_filtered(
+        notification: &MIDINotification,
+        filter: NotificationFilter,
+    ) -> Option<Result<Self, i32>> {
+        let message_id = notification.messageID as ::std::os::raw::c_uint;
+        match NotificationFilter::for_message_id(message_id) {
+            Some(category) if !filter.contains(category) => None,
+            _ => Some(Self::from(notification)),
+        }
+    }
+
     pub fn from(notification: &MIDINotification) -> Result<Self, i32> {
         match notification.messageID as ::std::os::raw::c_uint {
             kMIDIMsgSetupChanged => Ok(Self::SetupChanged),
@@ -80,44 +192,36 @@ impl Notification {
     fn from_object_added_removed(notification: &MIDINotification) -> Result<Self, i32> {
         let add_remove_notification =
             unsafe { &*(notification as *const _ as *const MIDIObjectAddRemoveNotification) };
-        let parent_type = ObjectType::from(add_remove_notification.parentType);
-        let child_type = ObjectType::from(add_remove_notification.childType);
-        if parent_type.is_ok() && child_type.is_ok() {
-            let add_remove_info = AddedRemovedInfo {
-                parent: Object(add_remove_notification.parent),
-                parent_type: parent_type.unwrap(),
-                child: Object(add_remove_notification.child),
-                child_type: child_type.unwrap(),
-            };
-            match notification.messageID as ::std::os::raw::c_uint {
-                kMIDIMsgObjectAdded => Ok(Self::ObjectAdded(add_remove_info)),
-                kMIDIMsgObjectRemoved => Ok(Self::ObjectRemoved(add_remove_info)),
-                _ => Err(0), // Never reached
-            }
-        } else {
-            Err(notification.messageID as i32)
+        let add_remove_info = AddedRemovedInfo {
+            parent: Object(add_remove_notification.parent),
+            parent_type: ObjectType::from(add_remove_notification.parentType),
+            child: Object(add_remove_notification.child),
+            child_type: ObjectType::from(add_remove_notification.childType),
+        };
+        match notification.messageID as ::std::os::raw::c_uint {
+            kMIDIMsgObjectAdded => Ok(Self::ObjectAdded(add_remove_info)),
+            kMIDIMsgObjectRemoved => Ok(Self::ObjectRemoved(add_remove_info)),
+            _ => Err(0), // Never reached
         }
     }
 
     fn from_property_changed(notification: &MIDINotification) -> Result<Notification, i32> {
         let property_changed_notification =
             unsafe { &*(notification as *const _ as *const MIDIObjectPropertyChangeNotification) };
-        match ObjectType::from(property_changed_notification.objectType) {
-            Ok(object_type) => {
-                let property_name = {
-                    let name_ref: CFStringRef = property_changed_notification.propertyName;
-                    let name: CFString = unsafe { TCFType::wrap_under_get_rule(name_ref) };
-                    name.to_string()
-                };
-                let property_changed_info = PropertyChangedInfo {
-                    object: Object(property_changed_notification.object),
-                    object_type,
-                    property_name,
-                };
-                Ok(Self::PropertyChanged(property_changed_info))
-            }
-            Err(_) => Err(notification.messageID as i32),
-        }
+        let object_type = ObjectType::from(property_changed_notification.objectType);
+        let property_name = {
+            let name_ref: CFStringRef = property_changed_notification.propertyName;
+            let name: CFString = unsafe { TCFType::wrap_under_get_rule(name_ref) };
+            name.to_string()
+        };
+        let property = PropertyName::from(property_name.as_str());
+        let property_changed_info = PropertyChangedInfo {
+            object: Object(property_changed_notification.object),
+            object_type,
+            property_name,
+            property,
+        };
+        Ok(Self::PropertyChanged(property_changed_info))
     }
 
     fn from_io_error(notification: &MIDINotification) -> Result<Self, i32> {
@@ -164,9 +268,13 @@ mod tests {
         AddedRemovedInfo,
         IOErrorInfo,
         Notification,
+        NotificationFilter,
         PropertyChangedInfo,
     };
-    use object::ObjectType;
+    use object::{
+        ObjectType,
+        PropertyName,
+    };
     use Device;
     use Object;
 
@@ -247,7 +355,7 @@ mod tests {
     }
 
     #[test]
-    fn notification_from_object_added_removed_err() {
+    fn notification_from_object_added_removed_unknown_type() {
         let notification_raw = MIDIObjectAddRemoveNotification {
             messageID: kMIDIMsgObjectAdded as MIDINotificationMessageID,
             messageSize: 24,
@@ -261,24 +369,16 @@ mod tests {
             &*(&notification_raw as *const _ as *const MIDINotification)
         });
 
-        assert!(notification.is_err());
-        assert_eq!(notification.err().unwrap(), kMIDIMsgObjectAdded as i32);
+        assert!(notification.is_ok());
 
-        let notification_raw = MIDIObjectAddRemoveNotification {
-            messageID: kMIDIMsgObjectRemoved as MIDINotificationMessageID,
-            messageSize: 24,
-            parent: 1 as MIDIObjectRef,
-            parentType: 0xffff,
-            child: 2 as MIDIObjectRef,
-            childType: kMIDIObjectType_Device,
+        let info = AddedRemovedInfo {
+            parent: Object(1),
+            parent_type: ObjectType::Device,
+            child: Object(2),
+            child_type: ObjectType::Unknown(0xffff),
         };
 
-        let notification = Notification::from(unsafe {
-            &*(&notification_raw as *const _ as *const MIDINotification)
-        });
-
-        assert!(notification.is_err());
-        assert_eq!(notification.err().unwrap(), kMIDIMsgObjectRemoved as i32);
+        assert_eq!(notification.unwrap(), Notification::ObjectAdded(info));
     }
 
     #[test]
@@ -302,13 +402,14 @@ mod tests {
             object: Object(1),
             object_type: ObjectType::Device,
             property_name: "name".to_string(),
+            property: PropertyName::Name,
         };
 
         assert_eq!(notification.unwrap(), Notification::PropertyChanged(info));
     }
 
     #[test]
-    fn notification_from_property_changed_error() {
+    fn notification_from_property_changed_unknown_type() {
         let name = CFString::new("name");
         let notification_raw = MIDIObjectPropertyChangeNotification {
             messageID: kMIDIMsgPropertyChanged as MIDINotificationMessageID,
@@ -322,8 +423,16 @@ mod tests {
             &*(&notification_raw as *const _ as *const MIDINotification)
         });
 
-        assert!(notification.is_err());
-        assert_eq!(notification.err().unwrap(), kMIDIMsgPropertyChanged as i32);
+        assert!(notification.is_ok());
+
+        let info = PropertyChangedInfo {
+            object: Object(1),
+            object_type: ObjectType::Unknown(0xffff),
+            property_name: "name".to_string(),
+            property: PropertyName::Name,
+        };
+
+        assert_eq!(notification.unwrap(), Notification::PropertyChanged(info));
     }
 
     #[test]
@@ -370,4 +479,38 @@ mod tests {
 
         assert_eq!(notification.unwrap(), Notification::IOError(info));
     }
+
+    #[test]
+    fn notification_filter_all_contains_every_category() {
+        let all = NotificationFilter::all();
+        assert!(all.contains(NotificationFilter::SETUP_CHANGED));
+        assert!(all.contains(NotificationFilter::OBJECT_ADDED));
+        assert!(all.contains(NotificationFilter::OBJECT_REMOVED));
+        assert!(all.contains(NotificationFilter::PROPERTY_CHANGED));
+        assert!(all.contains(NotificationFilter::THRU_CONNECTIONS_CHANGED));
+        assert!(all.contains(NotificationFilter::SERIAL_PORT_OWNER_CHANGED));
+        assert!(all.contains(NotificationFilter::IO_ERROR));
+    }
+
+    #[test]
+    fn notification_from_filtered_skips_excluded_category() {
+        let notification_raw = MIDINotification {
+            messageID: kMIDIMsgSetupChanged as MIDINotificationMessageID,
+            messageSize: 8,
+        };
+        let filter = NotificationFilter::OBJECT_ADDED;
+        let notification = Notification::from_filtered(&notification_raw, filter);
+        assert!(notification.is_none());
+    }
+
+    #[test]
+    fn notification_from_filtered_delivers_included_category() {
+        let notification_raw = MIDINotification {
+            messageID: kMIDIMsgSetupChanged as MIDINotificationMessageID,
+            messageSize: 8,
+        };
+        let filter = NotificationFilter::SETUP_CHANGED;
+        let notification = Notification::from_filtered(&notification_raw, filter);
+        assert_eq!(notification, Some(Ok(Notification::SetupChanged)));
+    }
 }
@@ -38,25 +38,108 @@ pub enum ObjectType {
     ExternalEntity,
     ExternalSource,
     ExternalDestination,
+    /// A raw object type value not known to this version of the crate, kept
+    /// around instead of being dropped so a newer macOS can introduce new
+    /// object types without breaking notification parsing.
+    Unknown(i32),
 }
 
 impl ObjectType {
-    pub fn from(value: i32) -> Result<Self, i32> {
+    /// Converts a raw `MIDIObjectType` value. This never fails: values not
+    /// recognized by this version of the crate become `Unknown`.
+    ///
+    pub fn from(value: i32) -> Self {
         match value {
-            kMIDIObjectType_Other => Ok(Self::Other),
-            kMIDIObjectType_Device => Ok(Self::Device),
-            kMIDIObjectType_Entity => Ok(Self::Entity),
-            kMIDIObjectType_Source => Ok(Self::Source),
-            kMIDIObjectType_Destination => Ok(Self::Destination),
-            kMIDIObjectType_ExternalDevice => Ok(Self::ExternalDevice),
-            kMIDIObjectType_ExternalEntity => Ok(Self::ExternalEntity),
-            kMIDIObjectType_ExternalSource => Ok(Self::ExternalSource),
-            kMIDIObjectType_ExternalDestination => Ok(Self::ExternalDestination),
-            unknown => Err(unknown),
+            kMIDIObjectType_Other => Self::Other,
+            kMIDIObjectType_Device => Self::Device,
+            kMIDIObjectType_Entity => Self::Entity,
+            kMIDIObjectType_Source => Self::Source,
+            kMIDIObjectType_Destination => Self::Destination,
+            kMIDIObjectType_ExternalDevice => Self::ExternalDevice,
+            kMIDIObjectType_ExternalEntity => Self::ExternalEntity,
+            kMIDIObjectType_ExternalSource => Self::ExternalSource,
+            kMIDIObjectType_ExternalDestination => Self::ExternalDestination,
+            unknown => Self::Unknown(unknown),
+        }
+    }
+
+    /// Converts this `ObjectType` back to its raw `MIDIObjectType` value.
+    ///
+    pub fn to_raw(&self) -> i32 {
+        match *self {
+            Self::Other => kMIDIObjectType_Other,
+            Self::Device => kMIDIObjectType_Device,
+            Self::Entity => kMIDIObjectType_Entity,
+            Self::Source => kMIDIObjectType_Source,
+            Self::Destination => kMIDIObjectType_Destination,
+            Self::ExternalDevice => kMIDIObjectType_ExternalDevice,
+            Self::ExternalEntity => kMIDIObjectType_ExternalEntity,
+            Self::ExternalSource => kMIDIObjectType_ExternalSource,
+            Self::ExternalDestination => kMIDIObjectType_ExternalDestination,
+            Self::Unknown(raw) => raw,
         }
     }
 }
 
+impl From<ObjectType> for i32 {
+    fn from(object_type: ObjectType) -> Self {
+        object_type.to_raw()
+    }
+}
+
+/// The well-known CoreMIDI object properties this crate already wraps with
+/// typed accessors, classifying a raw property name such as the one
+/// received in a `PropertyChangedInfo`.
+///
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum PropertyName {
+    Name,
+    UniqueId,
+    Manufacturer,
+    Offline,
+    DisplayName,
+    /// A property this crate has no dedicated accessor for.
+    Other(String),
+}
+
+impl PropertyName {
+    /// The raw CoreMIDI property key for this property.
+    ///
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Self::Name => "name",
+            Self::UniqueId => "uniqueID",
+            Self::Manufacturer => "manufacturer",
+            Self::Offline => "offline",
+            Self::DisplayName => "displayName",
+            Self::Other(ref name) => name,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for PropertyName {
+    fn from(name: &'a str) -> Self {
+        match name {
+            "name" => Self::Name,
+            "uniqueID" => Self::UniqueId,
+            "manufacturer" => Self::Manufacturer,
+            "offline" => Self::Offline,
+            "displayName" => Self::DisplayName,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+/// A property value re-read in its proper CoreMIDI type, as returned by
+/// [`PropertyChangedInfo::value`](::notifications::PropertyChangedInfo::value).
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue {
+    String(String),
+    Integer(i32),
+    Boolean(bool),
+}
+
 impl Object {
     /// Get the name for the object.
     ///
@@ -140,7 +223,10 @@ impl fmt::Debug for Object {
 
 #[cfg(test)]
 mod tests {
-    use object::ObjectType;
+    use object::{
+        ObjectType,
+        PropertyName,
+    };
 
     use coremidi_sys::{
         kMIDIObjectType_Destination,
@@ -156,46 +242,66 @@ mod tests {
 
     #[test]
     fn objecttype_from() {
-        assert_eq!(
-            ObjectType::from(kMIDIObjectType_Other),
-            Ok(ObjectType::Other)
-        );
-        assert_eq!(
-            ObjectType::from(kMIDIObjectType_Device),
-            Ok(ObjectType::Device)
-        );
-        assert_eq!(
-            ObjectType::from(kMIDIObjectType_Entity),
-            Ok(ObjectType::Entity)
-        );
-        assert_eq!(
-            ObjectType::from(kMIDIObjectType_Source),
-            Ok(ObjectType::Source)
-        );
+        assert_eq!(ObjectType::from(kMIDIObjectType_Other), ObjectType::Other);
+        assert_eq!(ObjectType::from(kMIDIObjectType_Device), ObjectType::Device);
+        assert_eq!(ObjectType::from(kMIDIObjectType_Entity), ObjectType::Entity);
+        assert_eq!(ObjectType::from(kMIDIObjectType_Source), ObjectType::Source);
         assert_eq!(
             ObjectType::from(kMIDIObjectType_Destination),
-            Ok(ObjectType::Destination)
+            ObjectType::Destination
         );
         assert_eq!(
             ObjectType::from(kMIDIObjectType_ExternalDevice),
-            Ok(ObjectType::ExternalDevice)
+            ObjectType::ExternalDevice
         );
         assert_eq!(
             ObjectType::from(kMIDIObjectType_ExternalEntity),
-            Ok(ObjectType::ExternalEntity)
+            ObjectType::ExternalEntity
         );
         assert_eq!(
             ObjectType::from(kMIDIObjectType_ExternalSource),
-            Ok(ObjectType::ExternalSource)
+            ObjectType::ExternalSource
         );
         assert_eq!(
             ObjectType::from(kMIDIObjectType_ExternalDestination),
-            Ok(ObjectType::ExternalDestination)
+            ObjectType::ExternalDestination
         );
     }
 
     #[test]
-    fn objecttype_from_error() {
-        assert_eq!(ObjectType::from(0xffff as i32), Err(0xffff));
+    fn objecttype_from_unknown() {
+        assert_eq!(ObjectType::from(0xffff as i32), ObjectType::Unknown(0xffff));
+    }
+
+    #[test]
+    fn objecttype_to_raw() {
+        assert_eq!(ObjectType::Device.to_raw(), kMIDIObjectType_Device);
+        assert_eq!(ObjectType::Unknown(0xffff).to_raw(), 0xffff);
+    }
+
+    #[test]
+    fn propertyname_from_well_known() {
+        assert_eq!(PropertyName::from("name"), PropertyName::Name);
+        assert_eq!(PropertyName::from("uniqueID"), PropertyName::UniqueId);
+        assert_eq!(PropertyName::from("manufacturer"), PropertyName::Manufacturer);
+        assert_eq!(PropertyName::from("offline"), PropertyName::Offline);
+        assert_eq!(PropertyName::from("displayName"), PropertyName::DisplayName);
+    }
+
+    #[test]
+    fn propertyname_from_other() {
+        assert_eq!(
+            PropertyName::from("some-custom-property"),
+            PropertyName::Other("some-custom-property".to_string())
+        );
+    }
+
+    #[test]
+    fn propertyname_as_str_round_trips() {
+        assert_eq!(PropertyName::Offline.as_str(), "offline");
+        assert_eq!(
+            PropertyName::Other("custom".to_string()).as_str(),
+            "custom"
+        );
     }
 }
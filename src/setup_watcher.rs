@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use core_foundation::base::OSStatus;
+
+use coremidi_sys::MIDIObjectRef;
+
+use clients::Client;
+use notifications::Notification;
+use object::ObjectType;
+use Destination;
+use Destinations;
+use Device;
+use Devices;
+use Object;
+use Source;
+use Sources;
+
+/// A snapshot of the current MIDI setup (devices, entities, sources and
+/// destinations), indexed by the stable `MIDIObjectRef` handle carried by
+/// every notification so that add/remove/change deltas can be applied
+/// without re-reading properties from objects CoreMIDI may have already
+/// torn down. Also keeps a `unique_id()` index for callers that only have
+/// the persistent id (e.g. from a saved preference) and want the live
+/// object for it.
+///
+/// Entities have no dedicated type in this crate, so they're tracked as
+/// raw `Object`s.
+///
+#[derive(Debug, Default)]
+pub struct Setup {
+    devices: HashMap<MIDIObjectRef, Device>,
+    entities: HashMap<MIDIObjectRef, Object>,
+    sources: HashMap<MIDIObjectRef, Source>,
+    destinations: HashMap<MIDIObjectRef, Destination>,
+    unique_ids: HashMap<u32, MIDIObjectRef>,
+    handle_unique_ids: HashMap<MIDIObjectRef, u32>,
+}
+
+impl Setup {
+    fn rescan(&mut self) {
+        self.devices.clear();
+        self.entities.clear();
+        self.sources.clear();
+        self.destinations.clear();
+        self.unique_ids.clear();
+        self.handle_unique_ids.clear();
+
+        for device in Devices {
+            let object = device.object;
+            self.track_unique_id(&object);
+            for entity in device.entities() {
+                self.track_unique_id(&entity);
+                self.entities.insert(entity.0, entity);
+            }
+            self.devices.insert(object.0, device);
+        }
+        for source in Sources {
+            let object = source.object;
+            self.track_unique_id(&object);
+            self.sources.insert(object.0, source);
+        }
+        for destination in Destinations {
+            let object = destination.object;
+            self.track_unique_id(&object);
+            self.destinations.insert(object.0, destination);
+        }
+    }
+
+    fn track_unique_id(&mut self, object: &Object) {
+        self.untrack_unique_id(object);
+        if let Some(unique_id) = object.unique_id() {
+            self.unique_ids.insert(unique_id, object.0);
+            self.handle_unique_ids.insert(object.0, unique_id);
+        }
+    }
+
+    fn untrack_unique_id(&mut self, object: &Object) {
+        if let Some(unique_id) = self.handle_unique_ids.remove(&object.0) {
+            self.unique_ids.remove(&unique_id);
+        }
+    }
+
+    fn insert(&mut self, object_type: ObjectType, object: &Object) {
+        self.track_unique_id(object);
+        match object_type {
+            ObjectType::Device | ObjectType::ExternalDevice => {
+                self.devices.insert(
+                    object.0,
+                    Device {
+                        object: *object,
+                    },
+                );
+            }
+            ObjectType::Entity | ObjectType::ExternalEntity => {
+                self.entities.insert(object.0, *object);
+            }
+            ObjectType::Source | ObjectType::ExternalSource => {
+                self.sources.insert(
+                    object.0,
+                    Source {
+                        object: *object,
+                    },
+                );
+            }
+            ObjectType::Destination | ObjectType::ExternalDestination => {
+                self.destinations.insert(
+                    object.0,
+                    Destination {
+                        object: *object,
+                    },
+                );
+            }
+            ObjectType::Other | ObjectType::Unknown(_) => {}
+        }
+    }
+
+    fn remove(&mut self, object: &Object) {
+        self.untrack_unique_id(object);
+        self.devices.remove(&object.0);
+        self.entities.remove(&object.0);
+        self.sources.remove(&object.0);
+        self.destinations.remove(&object.0);
+    }
+
+    /// A consistent snapshot of the currently known devices.
+    ///
+    pub fn devices(&self) -> Vec<Device> {
+        self.devices.values().cloned().collect()
+    }
+
+    /// A consistent snapshot of the currently known entities.
+    ///
+    pub fn entities(&self) -> Vec<Object> {
+        self.entities.values().cloned().collect()
+    }
+
+    /// A consistent snapshot of the currently known sources.
+    ///
+    pub fn sources(&self) -> Vec<Source> {
+        self.sources.values().cloned().collect()
+    }
+
+    /// A consistent snapshot of the currently known destinations.
+    ///
+    pub fn destinations(&self) -> Vec<Destination> {
+        self.destinations.values().cloned().collect()
+    }
+
+    /// Looks up the live object currently known under `unique_id`, if any.
+    ///
+    pub fn object_for_unique_id(&self, unique_id: u32) -> Option<Object> {
+        self.unique_ids
+            .get(&unique_id)
+            .map(|&handle| Object(handle))
+    }
+}
+
+/// Tracks the live MIDI setup (devices, entities, sources and destinations)
+/// by subscribing to `Notification`s and applying them incrementally,
+/// mirroring the add/insert, remove/delete, change/remove-then-re-add
+/// handling of a udev-style device monitor.
+///
+/// ```no_run
+/// let watcher = coremidi::SetupWatcher::new("example-watcher", |_| {}).unwrap();
+/// println!("{} sources", watcher.sources().len());
+/// ```
+///
+pub struct SetupWatcher {
+    _client: Client,
+    setup: Arc<Mutex<Setup>>,
+}
+
+impl SetupWatcher {
+    /// Creates a watcher that keeps its snapshot up to date and calls
+    /// `on_change` whenever the set of endpoints may have changed.
+    ///
+    pub fn new<F>(name: &str, mut on_change: F) -> Result<Self, OSStatus>
+    where
+        F: FnMut(&Setup) + Send + 'static,
+    {
+        let setup = Arc::new(Mutex::new(Setup::default()));
+
+        let watcher_setup = Arc::clone(&setup);
+        let client = Client::new_with_notifications(name, move |notification| {
+            let mut setup = watcher_setup.lock().unwrap();
+            let changed = Self::apply(&mut setup, notification);
+            if changed {
+                on_change(&setup);
+            }
+        })?;
+
+        // Subscribe before the initial scan: anything added/removed/changed
+        // in between is caught by this scan, and a racing notification is
+        // harmless since both paths key by the same `MIDIObjectRef`.
+        setup.lock().unwrap().rescan();
+
+        Ok(Self {
+            _client: client,
+            setup,
+        })
+    }
+
+    fn apply(setup: &mut Setup, notification: &Notification) -> bool {
+        match *notification {
+            Notification::SetupChanged => {
+                setup.rescan();
+                true
+            }
+            Notification::ObjectAdded(ref info) => {
+                setup.insert(info.child_type, &info.child);
+                true
+            }
+            Notification::ObjectRemoved(ref info) => {
+                setup.remove(&info.child);
+                true
+            }
+            Notification::PropertyChanged(ref info) => {
+                // CoreMIDI has already applied the change by the time it
+                // notifies, so re-insert under the (possibly new) identity
+                // rather than re-deriving the old one from `info.object`.
+                setup.insert(info.object_type, &info.object);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// A consistent snapshot of the currently known devices.
+    ///
+    pub fn devices(&self) -> Vec<Device> {
+        self.setup.lock().unwrap().devices()
+    }
+
+    /// A consistent snapshot of the currently known entities.
+    ///
+    pub fn entities(&self) -> Vec<Object> {
+        self.setup.lock().unwrap().entities()
+    }
+
+    /// A consistent snapshot of the currently known sources.
+    ///
+    pub fn sources(&self) -> Vec<Source> {
+        self.setup.lock().unwrap().sources()
+    }
+
+    /// A consistent snapshot of the currently known destinations.
+    ///
+    pub fn destinations(&self) -> Vec<Destination> {
+        self.setup.lock().unwrap().destinations()
+    }
+
+    /// Looks up the live object currently known under `unique_id`, if any.
+    ///
+    pub fn object_for_unique_id(&self, unique_id: u32) -> Option<Object> {
+        self.setup.lock().unwrap().object_for_unique_id(unique_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object::ObjectType;
+    use notifications::{
+        AddedRemovedInfo,
+        Notification,
+        PropertyChangedInfo,
+    };
+    use setup_watcher::{
+        Setup,
+        SetupWatcher,
+    };
+    use Object;
+
+    #[test]
+    fn setup_apply_object_added_inserts() {
+        let mut setup = Setup::default();
+        let info = AddedRemovedInfo {
+            parent: Object(0),
+            parent_type: ObjectType::Device,
+            child: Object(1),
+            child_type: ObjectType::Source,
+        };
+
+        assert!(SetupWatcher::apply(
+            &mut setup,
+            &Notification::ObjectAdded(info)
+        ));
+
+        assert_eq!(setup.sources().len(), 1);
+        assert_eq!(setup.sources()[0].object, Object(1));
+    }
+
+    #[test]
+    fn setup_apply_object_removed_evicts_even_without_a_live_unique_id() {
+        let mut setup = Setup::default();
+        let added = AddedRemovedInfo {
+            parent: Object(0),
+            parent_type: ObjectType::Device,
+            child: Object(1),
+            child_type: ObjectType::Source,
+        };
+        SetupWatcher::apply(&mut setup, &Notification::ObjectAdded(added));
+        assert_eq!(setup.sources().len(), 1);
+
+        // By the time CoreMIDI delivers the removed notification the child
+        // object no longer resolves any properties (as in production), but
+        // eviction must still succeed because it is keyed by the raw handle.
+        let removed = AddedRemovedInfo {
+            parent: Object(0),
+            parent_type: ObjectType::Device,
+            child: Object(1),
+            child_type: ObjectType::Source,
+        };
+        assert!(SetupWatcher::apply(
+            &mut setup,
+            &Notification::ObjectRemoved(removed)
+        ));
+
+        assert!(setup.sources().is_empty());
+    }
+
+    #[test]
+    fn setup_apply_property_changed_reinserts_under_same_handle() {
+        let mut setup = Setup::default();
+        let added = AddedRemovedInfo {
+            parent: Object(0),
+            parent_type: ObjectType::Device,
+            child: Object(1),
+            child_type: ObjectType::Destination,
+        };
+        SetupWatcher::apply(&mut setup, &Notification::ObjectAdded(added));
+
+        let info = PropertyChangedInfo {
+            object: Object(1),
+            object_type: ObjectType::Destination,
+            property_name: "uniqueID".to_string(),
+            property: ::object::PropertyName::UniqueId,
+        };
+        assert!(SetupWatcher::apply(
+            &mut setup,
+            &Notification::PropertyChanged(info)
+        ));
+
+        assert_eq!(setup.destinations().len(), 1);
+        assert_eq!(setup.destinations()[0].object, Object(1));
+    }
+}